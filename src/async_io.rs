@@ -0,0 +1,152 @@
+//! Optional `tokio::io::AsyncRead`/`AsyncWrite` support, gated behind the
+//! `tokio` cargo feature.
+
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{MockStream, Read, Write};
+
+#[derive(Default)]
+struct Inner {
+    stream: MockStream,
+    read_wakers: Vec<Waker>,
+    read_closed: bool,
+}
+
+/// Async counterpart of [`SharedMockStream`](crate::SharedMockStream), backed by
+/// the same [`MockStream`] buffers but implementing tokio's `AsyncRead`/
+/// `AsyncWrite` instead of the blocking `Read`/`Write`.
+///
+/// A `poll_read` against an empty buffer simulates backpressure: it returns
+/// `Poll::Pending` and registers the current task's waker, which is woken the
+/// next time `push_bytes_to_read` supplies more data. This is the async
+/// equivalent of how `SyncMockStream::wait_for` blocks a synchronous read.
+/// Call [`close`](AsyncMockStream::close) once no more data will be pushed so
+/// that a reader waiting on EOF (e.g. `read_to_end`) can complete.
+#[derive(Clone, Default)]
+pub struct AsyncMockStream {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl AsyncMockStream {
+    /// Create empty stream
+    pub fn new() -> AsyncMockStream {
+        AsyncMockStream::default()
+    }
+
+    /// Provide data to be read by `AsyncRead::poll_read` calls, waking any
+    /// task that is currently pending on an empty buffer.
+    pub fn push_bytes_to_read(&mut self, bytes: &[u8]) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.stream.push_bytes_to_read(bytes);
+        for waker in inner.read_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Extract all bytes written by `AsyncWrite::poll_write` calls.
+    pub fn pop_bytes_written(&mut self) -> Vec<u8> {
+        self.inner.lock().unwrap().stream.pop_bytes_written()
+    }
+
+    /// Signal that no more data will ever be pushed. Once closed, a
+    /// `poll_read` against an empty buffer reports true end-of-stream
+    /// (`Poll::Ready(Ok(()))` with nothing written into `buf`) instead of
+    /// `Poll::Pending`, waking any task already parked on an empty read.
+    /// Without calling this, a reader looping until EOF (e.g.
+    /// `AsyncReadExt::read_to_end`) would hang forever.
+    pub fn close(&mut self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.read_closed = true;
+        for waker in inner.read_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl AsyncRead for AsyncMockStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut tmp = vec![0u8; buf.remaining()];
+        match inner.stream.read(&mut tmp) {
+            Ok(0) if inner.read_closed => Poll::Ready(Ok(())),
+            Ok(0) => {
+                inner.read_wakers.push(cx.waker().clone());
+                Poll::Pending
+            }
+            Ok(n) => {
+                buf.put_slice(&tmp[..n]);
+                Poll::Ready(Ok(()))
+            }
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+impl AsyncWrite for AsyncMockStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.inner.lock().unwrap().stream.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.inner.lock().unwrap().stream.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncMockStream;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_async_mock_stream_read_to_end_on_close() {
+        let mut s = AsyncMockStream::new();
+        s.push_bytes_to_read(b"abcd");
+        s.close();
+
+        let mut buf = Vec::new();
+        s.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, b"abcd");
+    }
+
+    #[tokio::test]
+    async fn test_async_mock_stream_read_pending_until_pushed() {
+        let mut s = AsyncMockStream::new();
+        let mut reader = s.clone();
+
+        let read = tokio::spawn(async move {
+            let mut buf = [0; 4];
+            reader.read_exact(&mut buf).await.unwrap();
+            buf
+        });
+
+        // give the reader a chance to park on the empty buffer first
+        tokio::task::yield_now().await;
+        s.push_bytes_to_read(b"abcd");
+
+        assert_eq!(read.await.unwrap(), *b"abcd");
+    }
+
+    #[tokio::test]
+    async fn test_async_mock_stream_write() {
+        let mut s = AsyncMockStream::new();
+        s.write_all(b"abcd").await.unwrap();
+        assert_eq!(s.pop_bytes_written(), b"abcd");
+    }
+}
@@ -1,19 +1,73 @@
 #![crate_name = "mockstream"]
 #![crate_type = "lib"]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! A reader/writer streams to mock real streams in tests.
-
+//!
+//! Disable the default `std` feature to build under `no_std` (backed by
+//! `alloc` and an in-tree `no_std_io` shim, since every published `no_std`
+//! io crate we could find is either unbuildable on current stable Rust or
+//! yanked from the registry); `SyncMockStream`, which relies on OS threads,
+//! is only available with `std`.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+mod no_std_io;
+#[cfg(not(feature = "std"))]
+pub use no_std_io::{Cursor, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+#[cfg(feature = "std")]
 use std::cell::RefCell;
-use std::io::{Cursor, Error, ErrorKind, Read, Result, Write};
+#[cfg(not(feature = "std"))]
+use core::cell::RefCell;
+
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+#[cfg(not(feature = "std"))]
+use alloc::collections::VecDeque;
+
+#[cfg(feature = "std")]
+pub use std::io::{Cursor, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+#[cfg(feature = "std")]
 use std::mem::swap;
+#[cfg(not(feature = "std"))]
+use core::mem::swap;
+
+#[cfg(feature = "std")]
 use std::rc::Rc;
+#[cfg(not(feature = "std"))]
+use alloc::rc::Rc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+// The thread-based `SyncMockStream` has no `no_std` equivalent (there is no
+// portable blocking primitive without an OS), so it and its imports stay
+// gated behind the default `std` feature; `MockStream`/`SharedMockStream`/
+// `FailingMockStream` work under either.
+#[cfg(feature = "std")]
 use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
 use std::sync::{Arc, Mutex};
+#[cfg(feature = "std")]
 use std::thread::sleep;
+#[cfg(feature = "std")]
 use std::time;
 
-#[cfg(test)]
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "tokio")]
+pub use async_io::AsyncMockStream;
+
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_tests;
+
+#[cfg(feature = "std")]
 fn find_subsequence<T>(haystack: &[T], needle: &[T]) -> Option<usize>
 where
     for<'a> &'a [T]: PartialEq,
@@ -28,6 +82,19 @@ where
 pub struct MockStream {
     reader: Cursor<Vec<u8>>,
     writer: Cursor<Vec<u8>>,
+    read_frames: VecDeque<Vec<u8>>,
+    max_read_size: usize,
+    max_write_size: usize,
+    fail_point: Option<ReadFailPoint>,
+    bytes_read: usize,
+}
+
+#[derive(Clone)]
+struct ReadFailPoint {
+    offset: usize,
+    kind: ErrorKind,
+    message: &'static str,
+    repeat_count: i32,
 }
 
 impl Default for MockStream {
@@ -46,6 +113,11 @@ impl MockStream {
         MockStream {
             reader: new_cursor(),
             writer: new_cursor(),
+            read_frames: VecDeque::new(),
+            max_read_size: usize::MAX,
+            max_write_size: usize::MAX,
+            fail_point: None,
+            bytes_read: 0,
         }
     }
 
@@ -62,25 +134,103 @@ impl MockStream {
         result
     }
 
-    /// Provide data to be read by Read trait calls.
+    /// Provide data to be read by Read trait calls. Appended after whatever is
+    /// already buffered, so bytes that haven't been read yet (including ones
+    /// skipped over with `seek`, rather than consumed via `read`) are never
+    /// discarded.
     pub fn push_bytes_to_read(&mut self, bytes: &[u8]) {
-        let avail = self.reader.get_ref().len();
-        if self.reader.position() == avail as u64 {
-            self.reader = new_cursor();
-        }
         self.reader.get_mut().extend(bytes.iter().copied());
     }
+
+    /// Queue a separate frame to be returned by its own `read` call, instead of
+    /// merging it into the single buffer used by `push_bytes_to_read`. Frames
+    /// are drained in FIFO order, one per `read`, and take priority over any
+    /// bytes previously provided via `push_bytes_to_read`. Use this to simulate
+    /// message boundaries and assert on the number of reads a client performs.
+    pub fn push_read_frame(&mut self, bytes: &[u8]) {
+        self.read_frames.push_back(bytes.to_vec());
+    }
+
+    /// Cap every individual `read` call to at most `size` bytes, regardless of
+    /// how much data is buffered or how large the caller's slice is. Useful for
+    /// exercising code that must reassemble short reads. Defaults to unlimited.
+    pub fn set_max_read_size(&mut self, size: usize) {
+        self.max_read_size = size;
+    }
+
+    /// Cap every individual `write` call to at most `size` bytes, regardless of
+    /// how large the caller's slice is. Useful for exercising code that must
+    /// handle short writes. Defaults to unlimited.
+    pub fn set_max_write_size(&mut self, size: usize) {
+        self.max_write_size = size;
+    }
+
+    /// Seek the write-side cursor, independently of the `Seek` impl on this
+    /// stream (which only moves the read-side cursor).
+    pub fn seek_write(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.writer.seek(pos)
+    }
+
+    /// Serve buffered data normally until `offset` bytes have been read in
+    /// total, then have the next `repeat_count` `read` calls (with the same
+    /// negative-means-indefinitely, zero-means-never convention as
+    /// `FailingMockStream`) return an error of `kind`/`message` instead of
+    /// more data. Reads resume normally once the repeat count is
+    /// exhausted. Simulates a connection that drops partway through a
+    /// response.
+    pub fn fail_after(&mut self, offset: usize, kind: ErrorKind, message: &'static str, repeat_count: i32) {
+        if repeat_count == 0 {
+            self.fail_point = None;
+            return;
+        }
+        self.fail_point = Some(ReadFailPoint {
+            offset,
+            kind,
+            message,
+            repeat_count,
+        });
+    }
 }
 
 impl Read for MockStream {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        self.reader.read(buf)
+        if let Some(fp) = &mut self.fail_point {
+            if self.bytes_read >= fp.offset {
+                let kind = fp.kind;
+                let message = fp.message;
+                if fp.repeat_count > 0 {
+                    fp.repeat_count -= 1;
+                }
+                if fp.repeat_count == 0 {
+                    self.fail_point = None;
+                }
+                return Err(Error::new(kind, message));
+            }
+        }
+        let mut cap = self.max_read_size.min(buf.len());
+        if let Some(fp) = &self.fail_point {
+            cap = cap.min(fp.offset - self.bytes_read);
+        }
+        let n = if let Some(mut frame) = self.read_frames.pop_front() {
+            let n = frame.len().min(cap);
+            buf[..n].copy_from_slice(&frame[..n]);
+            if n < frame.len() {
+                frame.drain(..n);
+                self.read_frames.push_front(frame);
+            }
+            n
+        } else {
+            self.reader.read(&mut buf[..cap])?
+        };
+        self.bytes_read += n;
+        Ok(n)
     }
 }
 
 impl Write for MockStream {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
-        self.writer.write(buf)
+        let n = self.max_write_size.min(buf.len());
+        self.writer.write(&buf[..n])
     }
 
     fn flush(&mut self) -> Result<()> {
@@ -88,6 +238,13 @@ impl Write for MockStream {
     }
 }
 
+impl Seek for MockStream {
+    /// Seek the read-side cursor. Use `seek_write` to seek the write-side one.
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.reader.seek(pos)
+    }
+}
+
 /// Reference-counted stream.
 #[derive(Clone, Default)]
 pub struct SharedMockStream {
@@ -105,10 +262,32 @@ impl SharedMockStream {
         self.pimpl.borrow_mut().push_bytes_to_read(bytes)
     }
 
+    /// Queue a separate frame to be returned by its own `read` call.
+    pub fn push_read_frame(&mut self, bytes: &[u8]) {
+        self.pimpl.borrow_mut().push_read_frame(bytes)
+    }
+
     /// Provide data to be read by Read trait calls.
     pub fn pop_bytes_written(&mut self) -> Vec<u8> {
         self.pimpl.borrow_mut().pop_bytes_written()
     }
+
+    /// Cap every individual `read` call to at most `size` bytes.
+    pub fn set_max_read_size(&mut self, size: usize) {
+        self.pimpl.borrow_mut().set_max_read_size(size)
+    }
+
+    /// Cap every individual `write` call to at most `size` bytes.
+    pub fn set_max_write_size(&mut self, size: usize) {
+        self.pimpl.borrow_mut().set_max_write_size(size)
+    }
+
+    /// Fail reads at a given offset. See `MockStream::fail_after`.
+    pub fn fail_after(&mut self, offset: usize, kind: ErrorKind, message: &'static str, repeat_count: i32) {
+        self.pimpl
+            .borrow_mut()
+            .fail_after(offset, kind, message, repeat_count)
+    }
 }
 
 impl Read for SharedMockStream {
@@ -127,7 +306,9 @@ impl Write for SharedMockStream {
     }
 }
 
-/// Thread-safe stream.
+/// Thread-safe stream. Requires the `std` feature (there is no portable
+/// blocking primitive to build this on under `no_std`).
+#[cfg(feature = "std")]
 #[derive(Clone, Default)]
 pub struct SyncMockStream {
     pimpl: Arc<Mutex<MockStream>>,
@@ -135,6 +316,7 @@ pub struct SyncMockStream {
     pub expected_bytes: Vec<u8>,
 }
 
+#[cfg(feature = "std")]
 impl SyncMockStream {
     /// Create empty stream
     pub fn new() -> SyncMockStream {
@@ -152,12 +334,36 @@ impl SyncMockStream {
         self.pimpl.lock().unwrap().push_bytes_to_read(bytes)
     }
 
+    /// Queue a separate frame to be returned by its own `read` call.
+    pub fn push_read_frame(&mut self, bytes: &[u8]) {
+        self.pimpl.lock().unwrap().push_read_frame(bytes)
+    }
+
     /// Provide data to be read by Read trait calls.
     pub fn pop_bytes_written(&mut self) -> Vec<u8> {
         self.pimpl.lock().unwrap().pop_bytes_written()
     }
+
+    /// Cap every individual `read` call to at most `size` bytes.
+    pub fn set_max_read_size(&mut self, size: usize) {
+        self.pimpl.lock().unwrap().set_max_read_size(size)
+    }
+
+    /// Cap every individual `write` call to at most `size` bytes.
+    pub fn set_max_write_size(&mut self, size: usize) {
+        self.pimpl.lock().unwrap().set_max_write_size(size)
+    }
+
+    /// Fail reads at a given offset. See `MockStream::fail_after`.
+    pub fn fail_after(&mut self, offset: usize, kind: ErrorKind, message: &'static str, repeat_count: i32) {
+        self.pimpl
+            .lock()
+            .unwrap()
+            .fail_after(offset, kind, message, repeat_count)
+    }
 }
 
+#[cfg(feature = "std")]
 impl Read for SyncMockStream {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         while self.waiting_for_write.load(Ordering::Relaxed) {
@@ -167,6 +373,7 @@ impl Read for SyncMockStream {
     }
 }
 
+#[cfg(feature = "std")]
 impl Write for SyncMockStream {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
         let mut x = self.pimpl.lock().unwrap();
@@ -193,12 +400,12 @@ impl Write for SyncMockStream {
 /// # Examples
 ///
 /// ```
-/// use std::io::{Cursor, Read};
+/// use mockstream::{Cursor, ErrorKind, FailingMockStream, Read};
 ///
 /// struct CountIo {}
 ///
 /// impl CountIo {
-///     fn read_data(&self, r: &mut Read) -> usize {
+///     fn read_data(&self, r: &mut dyn Read) -> usize {
 ///         let mut count: usize = 0;
 ///         let mut retries = 3;
 ///
@@ -217,16 +424,13 @@ impl Write for SyncMockStream {
 ///     }
 /// }
 ///
-/// #[test]
-/// fn test_io_retries() {
-///     let mut c = Cursor::new(&b"1234"[..])
-///             .chain(FailingMockStream::new(ErrorKind::Other, "Failing", 3))
-///             .chain(Cursor::new(&b"5678"[..]));
+/// let mut s = Cursor::new(&b"1234"[..])
+///     .chain(FailingMockStream::new(ErrorKind::Other, "Failing", 3))
+///     .chain(Cursor::new(&b"5678"[..]));
 ///
-///     let sut = CountIo {};
-///     // this will fail unless read_data performs at least 3 retries on I/O errors
-///     assert_eq!(8, sut.read_data(&mut c));
-/// }
+/// let sut = CountIo {};
+/// // this will fail unless read_data performs at least 3 retries on I/O errors
+/// assert_eq!(8, sut.read_data(&mut s));
 /// ```
 #[derive(Clone)]
 pub struct FailingMockStream {
@@ -0,0 +1,157 @@
+//! A minimal `no_std`-compatible stand-in for the slice of `std::io` this
+//! crate needs (`Read`, `Write`, `Seek`, `Cursor<Vec<u8>>`, `Error`). Kept
+//! in-tree rather than pulled in as a dependency: every published `no_std`
+//! io crate we could find either fails to build on current stable Rust
+//! (`core_io`, whose build script only knows rustc versions up to 2021 and
+//! whose source needs feature gates removed in 1.53) or is yanked from the
+//! registry entirely (`core2`).
+
+use alloc::vec::Vec;
+use core::cmp::min;
+use core::fmt;
+
+/// Mirrors the subset of `std::io::ErrorKind` variants this crate and its
+/// users are likely to need.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    NotFound,
+    PermissionDenied,
+    ConnectionRefused,
+    ConnectionReset,
+    ConnectionAborted,
+    NotConnected,
+    BrokenPipe,
+    AlreadyExists,
+    WouldBlock,
+    InvalidInput,
+    InvalidData,
+    TimedOut,
+    WriteZero,
+    Interrupted,
+    UnexpectedEof,
+    Other,
+}
+
+/// A minimal error type carrying a kind and a static message, in place of
+/// `std::io::Error`.
+#[derive(Clone, Copy, Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    message: &'static str,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, message: &'static str) -> Error {
+        Error { kind, message }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+pub trait Read {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+}
+
+pub trait Write {
+    fn write(&mut self, buf: &[u8]) -> Result<usize>;
+    fn flush(&mut self) -> Result<()>;
+}
+
+pub enum SeekFrom {
+    Start(u64),
+    End(i64),
+    Current(i64),
+}
+
+pub trait Seek {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+}
+
+/// In-memory `Read`/`Write`/`Seek` cursor over a growable byte buffer,
+/// mirroring the slice of `std::io::Cursor<Vec<u8>>`'s API this crate uses.
+#[derive(Clone)]
+pub struct Cursor<T> {
+    inner: T,
+    position: u64,
+}
+
+impl Cursor<Vec<u8>> {
+    pub fn new(inner: Vec<u8>) -> Cursor<Vec<u8>> {
+        Cursor { inner, position: 0 }
+    }
+
+    pub fn get_ref(&self) -> &Vec<u8> {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.inner
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: u64) {
+        self.position = position;
+    }
+}
+
+impl Read for Cursor<Vec<u8>> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let start = min(self.position as usize, self.inner.len());
+        let available = &self.inner[start..];
+        let n = min(buf.len(), available.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl Write for Cursor<Vec<u8>> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let start = self.position as usize;
+        if start > self.inner.len() {
+            self.inner.resize(start, 0);
+        }
+        let end = start + buf.len();
+        if end > self.inner.len() {
+            self.inner.resize(end, 0);
+        }
+        self.inner[start..end].copy_from_slice(buf);
+        self.position = end as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for Cursor<Vec<u8>> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => self.inner.len() as i64 + offset,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+        };
+        if new_position < 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            ));
+        }
+        self.position = new_position as u64;
+        Ok(self.position)
+    }
+}
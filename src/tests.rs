@@ -1,6 +1,5 @@
 use super::{FailingMockStream, MockStream, SharedMockStream, SyncMockStream};
-use std::error::Error;
-use std::io::{Cursor, ErrorKind, Read, Result, Write};
+use std::io::{BufReader, Cursor, ErrorKind, Read, Result, Seek, SeekFrom, Write};
 
 #[test]
 fn test_mock_stream_read() {
@@ -30,11 +29,135 @@ fn test_mock_stream_empty_and_fill() {
     assert_eq!(s.read(v.as_mut()).unwrap(), 0);
 }
 
+#[test]
+fn test_mock_stream_max_read_size() {
+    let mut s = MockStream::new();
+    s.push_bytes_to_read(b"abcdef");
+    s.set_max_read_size(2);
+    let mut v = [0; 6];
+    assert_eq!(s.read(v.as_mut()).unwrap(), 2);
+    assert_eq!(&v[..2], b"ab");
+    assert_eq!(s.read(v.as_mut()).unwrap(), 2);
+    assert_eq!(&v[..2], b"cd");
+    assert_eq!(s.read(v.as_mut()).unwrap(), 2);
+    assert_eq!(&v[..2], b"ef");
+}
+
+#[test]
+fn test_mock_stream_max_write_size() {
+    let mut s = MockStream::new();
+    s.set_max_write_size(2);
+    assert_eq!(s.write(b"abcdef").unwrap(), 2);
+    assert_eq!(s.write(b"cdef").unwrap(), 2);
+    assert_eq!(s.pop_bytes_written(), b"abcd");
+}
+
+#[test]
+fn test_mock_stream_push_read_frame() {
+    let mut s = MockStream::new();
+    s.push_read_frame(b"abc");
+    s.push_read_frame(b"de");
+    let mut v = [0; 8];
+    assert_eq!(s.read(v.as_mut()).unwrap(), 3);
+    assert_eq!(&v[..3], b"abc");
+    assert_eq!(s.read(v.as_mut()).unwrap(), 2);
+    assert_eq!(&v[..2], b"de");
+    assert_eq!(s.read(v.as_mut()).unwrap(), 0);
+}
+
+#[test]
+fn test_mock_stream_read_frame_priority_over_bytes() {
+    let mut s = MockStream::new();
+    s.push_bytes_to_read(b"xyz");
+    s.push_read_frame(b"abc");
+    let mut v = [0; 8];
+    assert_eq!(s.read(v.as_mut()).unwrap(), 3);
+    assert_eq!(&v[..3], b"abc");
+    assert_eq!(s.read(v.as_mut()).unwrap(), 3);
+    assert_eq!(&v[..3], b"xyz");
+}
+
+#[test]
+fn test_mock_stream_seek_rewind_and_reread() {
+    let mut s = MockStream::new();
+    s.push_bytes_to_read(b"abcdef");
+    let mut v = [0; 3];
+    assert_eq!(s.read(v.as_mut()).unwrap(), 3);
+    assert_eq!(&v, b"abc");
+
+    s.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(s.read(v.as_mut()).unwrap(), 3);
+    assert_eq!(&v, b"abc");
+
+    assert_eq!(s.seek(SeekFrom::End(0)).unwrap(), 6);
+    assert_eq!(s.read(v.as_mut()).unwrap(), 0);
+}
+
+#[test]
+fn test_mock_stream_seek_past_unread_then_push_preserves_unread_bytes() {
+    let mut s = MockStream::new();
+    s.push_bytes_to_read(b"abcdef");
+    let mut v = [0; 3];
+    assert_eq!(s.read(v.as_mut()).unwrap(), 3);
+    assert_eq!(&v, b"abc");
+
+    // Seeking to the end skips "def" without reading it; it must still be
+    // there if we seek back for it later.
+    assert_eq!(s.seek(SeekFrom::End(0)).unwrap(), 6);
+    s.push_bytes_to_read(b"XYZ");
+
+    s.seek(SeekFrom::Start(3)).unwrap();
+    let mut rest = Vec::new();
+    s.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"defXYZ");
+}
+
+#[test]
+fn test_mock_stream_seek_write() {
+    let mut s = MockStream::new();
+    s.write_all(b"abcdef").unwrap();
+    s.seek_write(SeekFrom::Start(0)).unwrap();
+    s.write_all(b"XY").unwrap();
+    assert_eq!(s.pop_bytes_written(), b"XYcdef");
+}
+
+#[test]
+fn test_mock_stream_fail_after() {
+    let mut s = MockStream::new();
+    s.push_bytes_to_read(b"abcdefgh");
+    s.fail_after(4, ErrorKind::ConnectionReset, "connection dropped", 1);
+
+    let mut v = [0; 8];
+    assert_eq!(s.read(v.as_mut()).unwrap(), 4);
+    assert_eq!(&v[..4], b"abcd");
+
+    let error = s.read(v.as_mut()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+    assert_eq!(error.to_string(), "connection dropped");
+
+    // repeat_count of 1 means the next read succeeds again
+    assert_eq!(s.read(v.as_mut()).unwrap(), 4);
+    assert_eq!(&v[..4], b"efgh");
+}
+
+#[test]
+fn test_mock_stream_fail_after_zero_repeat_count_never_fails() {
+    // Matches FailingMockStream::new's convention: repeat_count == 0 means
+    // the fail point never triggers.
+    let mut s = MockStream::new();
+    s.push_bytes_to_read(b"abcdefgh");
+    s.fail_after(4, ErrorKind::ConnectionReset, "connection dropped", 0);
+
+    let mut v = [0; 8];
+    assert_eq!(s.read(v.as_mut()).unwrap(), 8);
+    assert_eq!(&v, b"abcdefgh");
+}
+
 #[test]
 fn test_mock_stream_read_lines() {
     let mut s = MockStream::new();
     s.push_bytes_to_read("abcd\r\ndcba\r\n".as_bytes());
-    let first_line = s
+    let first_line = BufReader::new(s)
         .bytes()
         .map(|c| c.unwrap())
         .take_while(|&c| c != b'\n')
@@ -48,7 +171,7 @@ fn test_failing_mock_stream_read() {
     let mut v = [0; 4];
     let error = s.read(v.as_mut()).unwrap_err();
     assert_eq!(error.kind(), ErrorKind::BrokenPipe);
-    assert_eq!(error.description(), "The dog ate the ethernet cable");
+    assert_eq!(error.to_string(), "The dog ate the ethernet cable");
     // after a single error, it will return Ok(0)
     assert_eq!(s.read(v.as_mut()).unwrap(), 0);
 }
@@ -95,7 +218,7 @@ fn test_failing_mock_stream_write() {
     let mut s = FailingMockStream::new(ErrorKind::PermissionDenied, "Access denied", -1);
     let error = s.write("abcd".as_bytes()).unwrap_err();
     assert_eq!(error.kind(), ErrorKind::PermissionDenied);
-    assert_eq!(error.description(), "Access denied");
+    assert_eq!(error.to_string(), "Access denied");
     // it will keep failing
     s.write("abcd".as_bytes()).unwrap_err();
 }
@@ -136,7 +259,7 @@ impl Write for NetStream {
 /// read 4 bytes from network, reverse them and write back
 fn reverse4(s: &mut NetStream) -> Result<usize> {
     let mut v = [0; 4];
-    let count = try![s.read(v.as_mut())];
+    let count = s.read(v.as_mut())?;
     assert_eq!(count, 4);
     v.reverse();
     s.write(v.as_ref())